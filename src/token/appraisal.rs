@@ -0,0 +1,357 @@
+use super::*;
+use super::parser::{PlatSwComponent, RealmClaims};
+
+use std::collections::HashMap;
+
+/// Expected values for a single platform software component, matched by
+/// its title (`CCA_SW_COMP_TITLE`).
+#[derive(Debug, Clone)]
+pub struct SwComponentReference
+{
+    pub measurement: Vec<u8>,
+    pub signer_id: Vec<u8>,
+    pub mandatory: bool,
+}
+
+/// Reference values / endorsements an `AttestationClaims` appraisal is
+/// checked against.
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceValues
+{
+    pub rim: Option<Vec<u8>>,
+    pub rems: [Option<Vec<u8>>; CLAIM_COUNT_REALM_EXTENSIBLE_MEASUREMENTS],
+    pub personalization_value: Option<Vec<u8>>,
+    pub sw_components: HashMap<String, SwComponentReference>,
+}
+
+/// Per-claim appraisal outcome.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClaimVerdict
+{
+    Matched,
+    Mismatched { expected: Vec<u8>, actual: Vec<u8> },
+}
+
+impl ClaimVerdict
+{
+    pub fn is_match(&self) -> bool
+    {
+        matches!(self, ClaimVerdict::Matched)
+    }
+}
+
+/// Appraisal outcome for a single platform software component.
+#[derive(Debug, Clone)]
+pub struct SwComponentVerdict
+{
+    pub title: String,
+    pub measurement: ClaimVerdict,
+    pub signer_id: ClaimVerdict,
+}
+
+/// Structured result of appraising parsed claims against reference values.
+/// A pass/fail bool alone can't say which claim disagreed with the
+/// endorsements, so every checked claim gets its own verdict here.
+#[derive(Debug, Default)]
+pub struct AppraisalReport
+{
+    pub rim: Option<ClaimVerdict>,
+    pub personalization_value: Option<ClaimVerdict>,
+    pub rems: Vec<(usize, ClaimVerdict)>,
+    pub matched_components: Vec<SwComponentVerdict>,
+    pub mismatched_components: Vec<SwComponentVerdict>,
+    pub missing_components: Vec<String>,
+    pub unexpected_components: Vec<String>,
+    pub malformed_components: Vec<usize>,
+}
+
+impl AppraisalReport
+{
+    pub fn passed(&self) -> bool
+    {
+        self.rim.as_ref().map_or(true, ClaimVerdict::is_match)
+            && self.personalization_value.as_ref().map_or(true, ClaimVerdict::is_match)
+            && self.rems.iter().all(|(_, verdict)| verdict.is_match())
+            && self.mismatched_components.is_empty()
+            && self.missing_components.is_empty()
+            && self.unexpected_components.is_empty()
+            && self.malformed_components.is_empty()
+    }
+}
+
+fn compare(expected: &Option<Vec<u8>>, actual: &[u8]) -> Option<ClaimVerdict>
+{
+    expected.as_ref().map(|expected| {
+        if expected.as_slice() == actual {
+            ClaimVerdict::Matched
+        } else {
+            ClaimVerdict::Mismatched { expected: expected.clone(), actual: actual.to_vec() }
+        }
+    })
+}
+
+/// Check a realm token's claims (RIM, REMs, personalization value) against
+/// the reference values. Reference fields left as `None` are not checked.
+pub fn appraise_realm_claims(claims: &RealmClaims, reference: &ReferenceValues) -> AppraisalReport
+{
+    let mut report = AppraisalReport::default();
+
+    report.rim = compare(&reference.rim, &claims.rim);
+    report.personalization_value = compare(&reference.personalization_value, &claims.personalization_value);
+
+    for (index, expected_rem) in reference.rems.iter().enumerate() {
+        if let Some(verdict) = compare(expected_rem, &claims.rems[index]) {
+            report.rems.push((index, verdict));
+        }
+    }
+
+    report
+}
+
+/// Check a platform token's software components against the reference
+/// values, matching each present component by its title and flagging
+/// mismatched measurements, unexpected components, missing mandatory ones,
+/// and components whose claims are too malformed to even parse.
+pub fn appraise_sw_components(token: &PlatformToken, plat_hash_algo: &str, reference: &ReferenceValues) -> AppraisalReport
+{
+    let mut report = AppraisalReport::default();
+    let plat_hash_algo = plat_hash_algo.to_string();
+    let mut seen_titles = Vec::new();
+
+    let present_components = token
+        .sw_component_claims
+        .iter()
+        .enumerate()
+        .filter(|(_, component)| component.present);
+
+    for (index, component) in present_components {
+        let parsed = match PlatSwComponent::from_raw_claims(&component.claims, &plat_hash_algo) {
+            Ok(parsed) => parsed,
+            Err(_) => {
+                report.malformed_components.push(index);
+                continue;
+            }
+        };
+
+        seen_titles.push(parsed.ty.clone());
+
+        match reference.sw_components.get(&parsed.ty) {
+            Some(expected) => {
+                let verdict = SwComponentVerdict {
+                    title: parsed.ty.clone(),
+                    measurement: compare(&Some(expected.measurement.clone()), &parsed.value).unwrap(),
+                    signer_id: compare(&Some(expected.signer_id.clone()), &parsed.signer_id).unwrap(),
+                };
+
+                if verdict.measurement.is_match() && verdict.signer_id.is_match() {
+                    report.matched_components.push(verdict);
+                } else {
+                    report.mismatched_components.push(verdict);
+                }
+            }
+            None => report.unexpected_components.push(parsed.ty.clone()),
+        }
+    }
+
+    for (title, expected) in &reference.sw_components {
+        if expected.mandatory && !seen_titles.contains(title) {
+            report.missing_components.push(title.clone());
+        }
+    }
+
+    report
+}
+
+/// Appraise a full set of parsed claims (realm claims plus the platform
+/// token's software components) against the reference values in one pass.
+pub fn appraise(realm_claims: &RealmClaims, platform_token: &PlatformToken, plat_hash_algo: &str, reference: &ReferenceValues) -> AppraisalReport
+{
+    let mut report = appraise_realm_claims(realm_claims, reference);
+    let components = appraise_sw_components(platform_token, plat_hash_algo, reference);
+
+    report.matched_components = components.matched_components;
+    report.mismatched_components = components.mismatched_components;
+    report.missing_components = components.missing_components;
+    report.unexpected_components = components.unexpected_components;
+    report.malformed_components = components.malformed_components;
+
+    report
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn realm_claims() -> RealmClaims
+    {
+        RealmClaims {
+            challenge: vec![0u8; 8],
+            profile: None,
+            personalization_value: vec![1u8; 8],
+            hash_algo: "sha-256".to_string(),
+            pub_key_hash_algo: "sha-256".to_string(),
+            pub_key: vec![2u8; 8],
+            rim: vec![3u8; 8],
+            rems: Default::default(),
+        }
+    }
+
+    fn component(title: &str, measurement: Vec<u8>, signer_id: Vec<u8>) -> SwComponent
+    {
+        let mut claims = ClaimsMap::new();
+        claims.insert(CCA_SW_COMP_TITLE, Claim { mandatory: true, title: String::new(), present: true, data: ClaimData::Text(title.to_string()) });
+        claims.insert(CCA_SW_COMP_MEASUREMENT_VALUE, Claim { mandatory: true, title: String::new(), present: true, data: ClaimData::Bstr(measurement) });
+        claims.insert(CCA_SW_COMP_VERSION, Claim { mandatory: false, title: String::new(), present: true, data: ClaimData::Text("1.0".to_string()) });
+        claims.insert(CCA_SW_COMP_SIGNER_ID, Claim { mandatory: true, title: String::new(), present: true, data: ClaimData::Bstr(signer_id) });
+        SwComponent { present: true, claims }
+    }
+
+    fn malformed_component() -> SwComponent
+    {
+        // Missing every mandatory claim (title included), so parsing must fail.
+        SwComponent { present: true, claims: ClaimsMap::new() }
+    }
+
+    #[test]
+    fn realm_claims_match_reference_values()
+    {
+        let claims = realm_claims();
+        let reference = ReferenceValues { rim: Some(claims.rim.clone()), ..Default::default() };
+
+        let report = appraise_realm_claims(&claims, &reference);
+
+        assert!(report.passed());
+    }
+
+    #[test]
+    fn unexpected_component_fails_the_appraisal()
+    {
+        let mut token = PlatformToken::default();
+        token.sw_component_claims[0] = component("bl1", vec![9u8; 4], vec![8u8; 4]);
+
+        // No expected components at all, so the present one is unexpected.
+        let reference = ReferenceValues::default();
+
+        let report = appraise_sw_components(&token, "sha-256", &reference);
+
+        assert_eq!(report.unexpected_components, vec!["bl1".to_string()]);
+        assert!(!report.passed());
+    }
+
+    #[test]
+    fn malformed_component_is_reported_not_silently_dropped()
+    {
+        let mut token = PlatformToken::default();
+        token.sw_component_claims[0] = malformed_component();
+
+        let reference = ReferenceValues::default();
+
+        let report = appraise_sw_components(&token, "sha-256", &reference);
+
+        assert_eq!(report.malformed_components, vec![0]);
+        assert!(report.unexpected_components.is_empty());
+        assert!(!report.passed());
+    }
+
+    #[test]
+    fn matching_component_passes_the_appraisal()
+    {
+        let mut token = PlatformToken::default();
+        token.sw_component_claims[0] = component("bl1", vec![9u8; 4], vec![8u8; 4]);
+
+        let mut reference = ReferenceValues::default();
+        reference.sw_components.insert(
+            "bl1".to_string(),
+            SwComponentReference { measurement: vec![9u8; 4], signer_id: vec![8u8; 4], mandatory: true },
+        );
+
+        let report = appraise_sw_components(&token, "sha-256", &reference);
+
+        assert_eq!(report.matched_components.len(), 1);
+        assert!(report.passed());
+    }
+
+    #[test]
+    fn missing_mandatory_component_fails_the_appraisal()
+    {
+        // The token carries no components at all.
+        let token = PlatformToken::default();
+
+        let mut reference = ReferenceValues::default();
+        reference.sw_components.insert(
+            "bl1".to_string(),
+            SwComponentReference { measurement: vec![9u8; 4], signer_id: vec![8u8; 4], mandatory: true },
+        );
+
+        let report = appraise_sw_components(&token, "sha-256", &reference);
+
+        assert_eq!(report.missing_components, vec!["bl1".to_string()]);
+        assert!(!report.passed());
+    }
+
+    #[test]
+    fn mismatched_measurement_fails_the_appraisal()
+    {
+        let mut token = PlatformToken::default();
+        token.sw_component_claims[0] = component("bl1", vec![0xffu8; 4], vec![8u8; 4]);
+
+        let mut reference = ReferenceValues::default();
+        reference.sw_components.insert(
+            "bl1".to_string(),
+            SwComponentReference { measurement: vec![9u8; 4], signer_id: vec![8u8; 4], mandatory: true },
+        );
+
+        let report = appraise_sw_components(&token, "sha-256", &reference);
+
+        assert_eq!(report.mismatched_components.len(), 1);
+        assert!(!report.mismatched_components[0].measurement.is_match());
+        assert!(report.mismatched_components[0].signer_id.is_match());
+        assert!(report.matched_components.is_empty());
+        assert!(!report.passed());
+    }
+
+    #[test]
+    fn appraise_joins_realm_claims_and_sw_components()
+    {
+        let claims = realm_claims();
+
+        let mut token = PlatformToken::default();
+        token.sw_component_claims[0] = component("bl1", vec![9u8; 4], vec![8u8; 4]);
+
+        let mut reference = ReferenceValues {
+            rim: Some(claims.rim.clone()),
+            personalization_value: Some(claims.personalization_value.clone()),
+            ..Default::default()
+        };
+        reference.sw_components.insert(
+            "bl1".to_string(),
+            SwComponentReference { measurement: vec![9u8; 4], signer_id: vec![8u8; 4], mandatory: true },
+        );
+
+        let report = appraise(&claims, &token, "sha-256", &reference);
+
+        assert!(report.rim.as_ref().unwrap().is_match());
+        assert!(report.personalization_value.as_ref().unwrap().is_match());
+        assert_eq!(report.matched_components.len(), 1);
+        assert!(report.passed());
+    }
+
+    #[test]
+    fn appraise_fails_when_either_half_disagrees()
+    {
+        let claims = realm_claims();
+
+        let mut token = PlatformToken::default();
+        // Not in the reference set at all.
+        token.sw_component_claims[0] = component("rogue", vec![9u8; 4], vec![8u8; 4]);
+
+        let reference = ReferenceValues { rim: Some(claims.rim.clone()), ..Default::default() };
+
+        let report = appraise(&claims, &token, "sha-256", &reference);
+
+        assert!(report.rim.as_ref().unwrap().is_match());
+        assert_eq!(report.unexpected_components, vec!["rogue".to_string()]);
+        assert!(!report.passed());
+    }
+}
@@ -0,0 +1,199 @@
+use super::*;
+use super::parser::{PlatClaims, PlatSwComponent, RealmClaims};
+
+use ciborium::value::Value;
+use coset::{iana, CoseSign1Builder, HeaderBuilder, TaggedCborSerializable};
+use p384::ecdsa::SigningKey as P384SigningKey;
+use signature::Signer;
+
+fn bstr(v: &[u8]) -> Value
+{
+    Value::Bytes(v.to_vec())
+}
+
+fn text(v: &str) -> Value
+{
+    Value::Text(v.to_string())
+}
+
+fn int(v: i64) -> Value
+{
+    Value::Integer(v.into())
+}
+
+fn realm_claims_to_cbor(claims: &RealmClaims) -> Value
+{
+    let mut map = vec![
+        (int(CCA_REALM_CHALLENGE as i64), bstr(&claims.challenge)),
+        (int(CCA_REALM_PERSONALIZATION_VALUE as i64), bstr(&claims.personalization_value)),
+        (int(CCA_REALM_HASH_ALGO_ID as i64), text(&claims.hash_algo)),
+        (int(CCA_REALM_PUB_KEY_HASH_ALGO_ID as i64), text(&claims.pub_key_hash_algo)),
+        (int(CCA_REALM_PUB_KEY as i64), bstr(&claims.pub_key)),
+        (int(CCA_REALM_INITIAL_MEASUREMENT as i64), bstr(&claims.rim)),
+    ];
+
+    if let Some(profile) = &claims.profile {
+        map.push((int(CCA_REALM_PROFILE as i64), text(profile)));
+    }
+
+    let rems = claims.rems.iter().map(|rem| bstr(rem)).collect();
+    map.push((int(CCA_REALM_EXTENSIBLE_MEASUREMENTS as i64), Value::Array(rems)));
+
+    Value::Map(map)
+}
+
+fn sw_component_to_cbor(component: &PlatSwComponent) -> Value
+{
+    Value::Map(vec![
+        (int(CCA_SW_COMP_TITLE as i64), text(&component.ty)),
+        (int(CCA_SW_COMP_HASH_ALGORITHM as i64), text(&component.hash_algo)),
+        (int(CCA_SW_COMP_MEASUREMENT_VALUE as i64), bstr(&component.value)),
+        (int(CCA_SW_COMP_VERSION as i64), text(&component.version)),
+        (int(CCA_SW_COMP_SIGNER_ID as i64), bstr(&component.signer_id)),
+    ])
+}
+
+fn platform_claims_to_cbor(claims: &PlatClaims, sw_components: &[PlatSwComponent]) -> Value
+{
+    let components = sw_components.iter().map(sw_component_to_cbor).collect();
+
+    Value::Map(vec![
+        (int(CCA_PLAT_CHALLENGE as i64), bstr(&claims.challenge)),
+        (int(CCA_PLAT_VERIFICATION_SERVICE as i64), text(&claims.verification_service)),
+        (int(CCA_PLAT_PROFILE as i64), text(&claims.profile)),
+        (int(CCA_PLAT_INSTANCE_ID as i64), bstr(&claims.instance_id)),
+        (int(CCA_PLAT_IMPLEMENTATION_ID as i64), bstr(&claims.implementation_id)),
+        (int(CCA_PLAT_SECURITY_LIFECYCLE as i64), int(claims.lifecycle)),
+        (int(CCA_PLAT_CONFIGURATION as i64), bstr(&claims.configuration)),
+        (int(CCA_PLAT_HASH_ALGO_ID as i64), text(&claims.hash_algo)),
+        (int(CCA_PLAT_SW_COMPONENTS as i64), Value::Array(components)),
+    ])
+}
+
+fn cbor_to_vec(value: &Value) -> Result<Vec<u8>, TokenError>
+{
+    let mut out = Vec::new();
+    ciborium::ser::into_writer(value, &mut out).or(Err(TokenError::InvalidTokenFormat("Failed to encode CBOR claims")))?;
+    Ok(out)
+}
+
+fn sign_cose_sign1(payload: Vec<u8>, key: &P384SigningKey) -> Result<Vec<u8>, TokenError>
+{
+    let protected = HeaderBuilder::new().algorithm(iana::Algorithm::ES384).build();
+
+    let sign1 = CoseSign1Builder::new()
+        .protected(protected)
+        .payload(payload)
+        .create_signature(&[], |tbs| key.sign(tbs).to_bytes().to_vec())
+        .build();
+
+    sign1.to_tagged_vec().or(Err(TokenError::InvalidTokenFormat("Failed to encode COSE_Sign1")))
+}
+
+/// Build and sign the CCA Realm Delegated Attestation Token from structured claims.
+pub fn build_realm_token(claims: &RealmClaims, rak: &P384SigningKey) -> Result<Vec<u8>, TokenError>
+{
+    let payload = cbor_to_vec(&realm_claims_to_cbor(claims))?;
+    sign_cose_sign1(payload, rak)
+}
+
+/// Build and sign the CCA Platform Attestation Token from structured claims.
+pub fn build_platform_token(
+    claims: &PlatClaims,
+    sw_components: &[PlatSwComponent],
+    pak: &P384SigningKey,
+) -> Result<Vec<u8>, TokenError>
+{
+    let payload = cbor_to_vec(&platform_claims_to_cbor(claims, sw_components))?;
+    sign_cose_sign1(payload, pak)
+}
+
+/// Assemble a signed realm token and a signed platform token into a tagged
+/// CCA token collection, ready to be handed to the parser/verifier.
+pub fn build_cca_token_collection(
+    realm_claims: &RealmClaims,
+    plat_claims: &PlatClaims,
+    sw_components: &[PlatSwComponent],
+    rak: &P384SigningKey,
+    pak: &P384SigningKey,
+) -> Result<Vec<u8>, TokenError>
+{
+    let realm_token = build_realm_token(realm_claims, rak)?;
+    let platform_token = build_platform_token(plat_claims, sw_components, pak)?;
+
+    let collection = Value::Map(vec![
+        (int(CCA_PLAT_TOKEN as i64), Value::Bytes(platform_token)),
+        (int(CCA_REALM_DELEGATED_TOKEN as i64), Value::Bytes(realm_token)),
+    ]);
+
+    cbor_to_vec(&Value::Tag(TAG_CCA_TOKEN, Box::new(collection)))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use p384::ecdsa::SigningKey;
+    use rand_core::OsRng;
+
+    fn sample_realm_claims(pub_key: Vec<u8>) -> RealmClaims
+    {
+        RealmClaims {
+            challenge: vec![0u8; 32],
+            profile: Some(CCA_REALM_PROFILE_VALUE_1_0.to_string()),
+            personalization_value: vec![1u8; 32],
+            hash_algo: "sha-256".to_string(),
+            pub_key_hash_algo: "sha-256".to_string(),
+            pub_key,
+            rim: vec![2u8; 32],
+            rems: Default::default(),
+        }
+    }
+
+    fn sample_plat_claims() -> PlatClaims
+    {
+        PlatClaims {
+            challenge: vec![3u8; 32],
+            verification_service: String::new(),
+            profile: CCA_PLAT_PROFILE_VALUE_1_0.to_string(),
+            instance_id: vec![4u8; 32],
+            implementation_id: vec![5u8; 32],
+            lifecycle: 0,
+            configuration: vec![],
+            hash_algo: "sha-256".to_string(),
+        }
+    }
+
+    #[test]
+    fn token_collection_is_tagged_and_keyed_by_both_tokens()
+    {
+        let rak = SigningKey::random(&mut OsRng);
+        let pak = SigningKey::random(&mut OsRng);
+
+        let realm_claims = sample_realm_claims(rak.verifying_key().to_sec1_bytes().to_vec());
+        let plat_claims = sample_plat_claims();
+
+        let realm_token = build_realm_token(&realm_claims, &rak).expect("sign realm token");
+        let platform_token = build_platform_token(&plat_claims, &[], &pak).expect("sign platform token");
+
+        let collection = build_cca_token_collection(&realm_claims, &plat_claims, &[], &rak, &pak)
+            .expect("build token collection");
+
+        let decoded: Value = ciborium::de::from_reader(collection.as_slice()).expect("decode the collection as CBOR");
+
+        let (tag, inner) = match decoded {
+            Value::Tag(tag, inner) => (tag, *inner),
+            other => panic!("expected a tagged value, got {:?}", other),
+        };
+        assert_eq!(tag, TAG_CCA_TOKEN);
+
+        let map = match inner {
+            Value::Map(map) => map,
+            other => panic!("expected a map, got {:?}", other),
+        };
+
+        assert_eq!(map.len(), 2);
+        assert!(map.contains(&(int(CCA_PLAT_TOKEN as i64), Value::Bytes(platform_token))));
+        assert!(map.contains(&(int(CCA_REALM_DELEGATED_TOKEN as i64), Value::Bytes(realm_token))));
+    }
+}
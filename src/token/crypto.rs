@@ -0,0 +1,187 @@
+use super::TokenError;
+
+use coset::{iana, Algorithm};
+use ed25519_dalek::{Signature as Ed25519Signature, VerifyingKey as Ed25519VerifyingKey};
+use p256::ecdsa::{Signature as P256Signature, VerifyingKey as P256VerifyingKey};
+use p384::ecdsa::{Signature as P384Signature, VerifyingKey as P384VerifyingKey};
+use p521::ecdsa::{Signature as P521Signature, VerifyingKey as P521VerifyingKey};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use signature::Verifier;
+
+/// Signature algorithms accepted for a COSE_Sign1 `RealmToken`/`PlatformToken`
+/// signature, resolved from the protected header's `alg` claim.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SignatureAlgorithm
+{
+    Es256,
+    Es384,
+    Es512,
+    EdDsa,
+}
+
+impl SignatureAlgorithm
+{
+    pub(crate) fn from_cose_algorithm(alg: &Option<Algorithm>) -> Result<Self, TokenError>
+    {
+        match alg {
+            Some(Algorithm::Assigned(iana::Algorithm::ES256)) => Ok(Self::Es256),
+            Some(Algorithm::Assigned(iana::Algorithm::ES384)) => Ok(Self::Es384),
+            Some(Algorithm::Assigned(iana::Algorithm::ES512)) => Ok(Self::Es512),
+            Some(Algorithm::Assigned(iana::Algorithm::EdDSA)) => Ok(Self::EdDsa),
+            other => Err(TokenError::InvalidAlgorithm(other.clone())),
+        }
+    }
+
+    /// Verify `signature` over `signed_data` using `pub_key`, which must be
+    /// encoded the way the matching curve/scheme expects it (SEC1 for the
+    /// ECDSA curves, raw 32 bytes for Ed25519).
+    pub(crate) fn verify(&self, pub_key: &[u8], signed_data: &[u8], signature: &[u8]) -> Result<(), TokenError>
+    {
+        match self {
+            Self::Es256 => {
+                let key = P256VerifyingKey::from_sec1_bytes(pub_key)
+                    .or(Err(TokenError::InvalidKey("ES256 public key")))?;
+                let sig = P256Signature::from_slice(signature)
+                    .or(Err(TokenError::InvalidKey("ES256 signature")))?;
+                key.verify(signed_data, &sig)
+                    .or(Err(TokenError::VerificationFailed("ES256 signature")))
+            }
+            Self::Es384 => {
+                let key = P384VerifyingKey::from_sec1_bytes(pub_key)
+                    .or(Err(TokenError::InvalidKey("ES384 public key")))?;
+                let sig = P384Signature::from_slice(signature)
+                    .or(Err(TokenError::InvalidKey("ES384 signature")))?;
+                key.verify(signed_data, &sig)
+                    .or(Err(TokenError::VerificationFailed("ES384 signature")))
+            }
+            Self::Es512 => {
+                let key = P521VerifyingKey::from_sec1_bytes(pub_key)
+                    .or(Err(TokenError::InvalidKey("ES512 public key")))?;
+                let sig = P521Signature::from_slice(signature)
+                    .or(Err(TokenError::InvalidKey("ES512 signature")))?;
+                key.verify(signed_data, &sig)
+                    .or(Err(TokenError::VerificationFailed("ES512 signature")))
+            }
+            Self::EdDsa => {
+                let key_bytes: [u8; 32] = pub_key
+                    .try_into()
+                    .or(Err(TokenError::InvalidKey("EdDSA public key")))?;
+                let key = Ed25519VerifyingKey::from_bytes(&key_bytes)
+                    .or(Err(TokenError::InvalidKey("EdDSA public key")))?;
+                let sig_bytes: [u8; 64] = signature
+                    .try_into()
+                    .or(Err(TokenError::InvalidKey("EdDSA signature")))?;
+                let sig = Ed25519Signature::from_bytes(&sig_bytes);
+                key.verify(signed_data, &sig)
+                    .or(Err(TokenError::VerificationFailed("EdDSA signature")))
+            }
+        }
+    }
+}
+
+/// Hash `data` with the digest algorithm named in a CCA `*_HASH_ALGO_ID`
+/// claim (e.g. `"sha-256"`, `"sha-384"`, `"sha-512"`).
+pub(crate) fn hash(algorithm: &str, data: &[u8]) -> Result<Vec<u8>, TokenError>
+{
+    match algorithm {
+        "sha-256" => Ok(Sha256::digest(data).to_vec()),
+        "sha-384" => Ok(Sha384::digest(data).to_vec()),
+        "sha-512" => Ok(Sha512::digest(data).to_vec()),
+        _ => Err(TokenError::NotImplemented("Unsupported hash algorithm")),
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use ed25519_dalek::SigningKey as Ed25519SigningKey;
+    use p256::ecdsa::SigningKey as P256SigningKey;
+    use p384::ecdsa::SigningKey as P384SigningKey;
+    use p521::ecdsa::SigningKey as P521SigningKey;
+    use rand_core::OsRng;
+    use signature::Signer;
+
+    #[test]
+    fn es256_sign_and_verify_round_trips()
+    {
+        let key = P256SigningKey::random(&mut OsRng);
+        let pub_key = key.verifying_key().to_sec1_bytes().to_vec();
+        let data = b"es256 test payload";
+        let signature: P256Signature = key.sign(data);
+
+        SignatureAlgorithm::Es256
+            .verify(&pub_key, data, signature.to_bytes().as_slice())
+            .expect("ES256 signature must verify");
+    }
+
+    #[test]
+    fn es384_sign_and_verify_round_trips()
+    {
+        let key = P384SigningKey::random(&mut OsRng);
+        let pub_key = key.verifying_key().to_sec1_bytes().to_vec();
+        let data = b"es384 test payload";
+        let signature: P384Signature = key.sign(data);
+
+        SignatureAlgorithm::Es384
+            .verify(&pub_key, data, signature.to_bytes().as_slice())
+            .expect("ES384 signature must verify");
+    }
+
+    #[test]
+    fn es512_sign_and_verify_round_trips()
+    {
+        let key = P521SigningKey::random(&mut OsRng);
+        let pub_key = key.verifying_key().to_sec1_bytes().to_vec();
+        let data = b"es512 test payload";
+        let signature: P521Signature = key.sign(data);
+
+        SignatureAlgorithm::Es512
+            .verify(&pub_key, data, signature.to_bytes().as_slice())
+            .expect("ES512 signature must verify");
+    }
+
+    #[test]
+    fn eddsa_sign_and_verify_round_trips()
+    {
+        let key = Ed25519SigningKey::generate(&mut OsRng);
+        let pub_key = key.verifying_key().to_bytes().to_vec();
+        let data = b"eddsa test payload";
+        let signature: Ed25519Signature = key.sign(data);
+
+        SignatureAlgorithm::EdDsa
+            .verify(&pub_key, data, &signature.to_bytes())
+            .expect("EdDSA signature must verify");
+    }
+
+    #[test]
+    fn verification_fails_against_the_wrong_key()
+    {
+        let key = P384SigningKey::random(&mut OsRng);
+        let other_key = P384SigningKey::random(&mut OsRng);
+        let pub_key = other_key.verifying_key().to_sec1_bytes().to_vec();
+        let data = b"payload signed by the wrong key";
+        let signature: P384Signature = key.sign(data);
+
+        let result = SignatureAlgorithm::Es384.verify(&pub_key, data, signature.to_bytes().as_slice());
+
+        assert_eq!(result, Err(TokenError::VerificationFailed("ES384 signature")));
+    }
+
+    #[test]
+    fn unsupported_algorithm_is_rejected()
+    {
+        let unsupported = Some(Algorithm::Assigned(iana::Algorithm::A128GCM));
+
+        assert_eq!(
+            SignatureAlgorithm::from_cose_algorithm(&unsupported),
+            Err(TokenError::InvalidAlgorithm(unsupported)),
+        );
+    }
+
+    #[test]
+    fn missing_algorithm_is_rejected()
+    {
+        assert_eq!(SignatureAlgorithm::from_cose_algorithm(&None), Err(TokenError::InvalidAlgorithm(None)));
+    }
+}
@@ -1,3 +1,5 @@
+pub(crate) mod appraisal;
+pub(crate) mod builder;
 pub(crate) mod crypto;
 pub(crate) mod dumper;
 pub(crate) mod parser;
@@ -321,6 +323,7 @@ pub enum TokenError
     Ecdsa(ecdsa::Error),
     MissingMandatoryClaim(u32),
     ClaimDataMisMatchType,
+    KeyBindingMismatch(verifier::KeyBindingResult),
 }
 
 impl std::fmt::Display for TokenError
@@ -376,6 +379,7 @@ impl PartialEq for TokenError
             (TokenError::Ecdsa(_s), TokenError::Ecdsa(_e)) => true,
             (TokenError::MissingMandatoryClaim(s), TokenError::MissingMandatoryClaim(e)) => s == e,
             (TokenError::ClaimDataMisMatchType, TokenError::ClaimDataMisMatchType) => true,
+            (TokenError::KeyBindingMismatch(s), TokenError::KeyBindingMismatch(e)) => s == e,
             (_, _) => false,
         }
     }
@@ -0,0 +1,216 @@
+use super::*;
+use super::crypto::SignatureAlgorithm;
+use super::parser::{PlatClaims, RealmClaims};
+
+/// Verify a COSE_Sign1 structure against `pub_key`, dispatching on the
+/// algorithm carried in its own protected header.
+fn verify_cose_sign1(cose_sign1: &CoseSign1, pub_key: &[u8]) -> Result<(), TokenError>
+{
+    let algorithm = SignatureAlgorithm::from_cose_algorithm(&cose_sign1.protected.header.alg)?;
+
+    cose_sign1
+        .verify_signature(&[], |signature, signed_data| algorithm.verify(pub_key, signed_data, signature))
+}
+
+/// Verify the realm token's signature against its own embedded realm public
+/// key, and return the parsed claims on success.
+pub(crate) fn verify_realm_token(token: &RealmToken) -> Result<RealmClaims, TokenError>
+{
+    let realm_claims = RealmClaims::from_raw_claims(&token.token_claims, &token.measurement_claims)?;
+
+    verify_cose_sign1(&token.cose_sign1, &realm_claims.pub_key)?;
+
+    Ok(realm_claims)
+}
+
+/// Verify the platform token's signature against the caller-supplied
+/// platform attestation key, and return the parsed claims on success.
+pub(crate) fn verify_platform_token(token: &PlatformToken, pub_key: &[u8]) -> Result<PlatClaims, TokenError>
+{
+    let plat_claims = PlatClaims::from_raw_claims(&token.token_claims)?;
+
+    verify_cose_sign1(&token.cose_sign1, pub_key)?;
+
+    Ok(plat_claims)
+}
+
+/// Outcome of checking that the platform token attests the exact realm key
+/// that signed the realm token, exposed so callers can see why an
+/// appraisal succeeded or failed rather than only getting a bool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBindingResult
+{
+    pub bound: bool,
+    pub computed_hash: Vec<u8>,
+    pub expected_hash: Vec<u8>,
+}
+
+/// Hash the realm public key with the algorithm it names, and compare the
+/// digest against the platform token's nonce claim.
+fn verify_realm_platform_binding(realm_claims: &RealmClaims, plat_claims: &PlatClaims) -> Result<KeyBindingResult, TokenError>
+{
+    let computed_hash = crypto::hash(&realm_claims.pub_key_hash_algo, &realm_claims.pub_key)?;
+    let expected_hash = plat_claims.challenge.clone();
+    let bound = computed_hash == expected_hash;
+
+    Ok(KeyBindingResult { bound, computed_hash, expected_hash })
+}
+
+/// The two tokens' claims once the full CCA appraisal chain has been
+/// verified: both signatures, and the realm/platform key binding.
+pub struct VerifiedAttestation
+{
+    pub realm_claims: RealmClaims,
+    pub plat_claims: PlatClaims,
+    pub binding: KeyBindingResult,
+}
+
+/// Verify a realm token and platform token pair end to end: the realm
+/// token against its own embedded public key, the platform token against
+/// `platform_pub_key`, and finally that the platform token attests the
+/// realm key that signed the realm token.
+pub fn verify_attestation_token(
+    platform_token: &PlatformToken,
+    realm_token: &RealmToken,
+    platform_pub_key: &[u8],
+) -> Result<VerifiedAttestation, TokenError>
+{
+    let plat_claims = verify_platform_token(platform_token, platform_pub_key)?;
+    let realm_claims = verify_realm_token(realm_token)?;
+    let binding = verify_realm_platform_binding(&realm_claims, &plat_claims)?;
+
+    if !binding.bound {
+        return Err(TokenError::KeyBindingMismatch(binding));
+    }
+
+    Ok(VerifiedAttestation { realm_claims, plat_claims, binding })
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use coset::TaggedCborSerializable;
+    use p384::ecdsa::SigningKey;
+    use rand_core::OsRng;
+
+    fn fill(map: &mut ClaimsMap, key: u32, data: ClaimData)
+    {
+        if let Some(claim) = map.get_mut(&key) {
+            claim.data = data;
+            claim.present = true;
+        }
+    }
+
+    fn sample_realm_claims(pub_key: Vec<u8>) -> RealmClaims
+    {
+        RealmClaims {
+            challenge: vec![0u8; 32],
+            profile: None,
+            personalization_value: vec![1u8; 32],
+            hash_algo: "sha-256".to_string(),
+            pub_key_hash_algo: "sha-256".to_string(),
+            pub_key,
+            rim: vec![2u8; 32],
+            rems: Default::default(),
+        }
+    }
+
+    fn sample_plat_claims(challenge: Vec<u8>) -> PlatClaims
+    {
+        PlatClaims {
+            challenge,
+            verification_service: String::new(),
+            profile: String::new(),
+            instance_id: vec![3u8; 32],
+            implementation_id: vec![4u8; 32],
+            lifecycle: 0,
+            configuration: vec![],
+            hash_algo: "sha-256".to_string(),
+        }
+    }
+
+    fn signed_realm_token(claims: &RealmClaims, rak: &SigningKey) -> RealmToken
+    {
+        let bytes = builder::build_realm_token(claims, rak).expect("sign realm token");
+
+        let mut token = RealmToken::new();
+        token.cose_sign1 = CoseSign1::from_tagged_slice(&bytes).expect("decode realm COSE_Sign1");
+
+        fill(&mut token.token_claims, CCA_REALM_CHALLENGE, ClaimData::Bstr(claims.challenge.clone()));
+        fill(&mut token.token_claims, CCA_REALM_PERSONALIZATION_VALUE, ClaimData::Bstr(claims.personalization_value.clone()));
+        fill(&mut token.token_claims, CCA_REALM_HASH_ALGO_ID, ClaimData::Text(claims.hash_algo.clone()));
+        fill(&mut token.token_claims, CCA_REALM_PUB_KEY_HASH_ALGO_ID, ClaimData::Text(claims.pub_key_hash_algo.clone()));
+        fill(&mut token.token_claims, CCA_REALM_PUB_KEY, ClaimData::Bstr(claims.pub_key.clone()));
+        fill(&mut token.token_claims, CCA_REALM_INITIAL_MEASUREMENT, ClaimData::Bstr(claims.rim.clone()));
+        for (index, rem) in claims.rems.iter().enumerate() {
+            fill(&mut token.measurement_claims, index as u32, ClaimData::Bstr(rem.clone()));
+        }
+
+        token
+    }
+
+    fn signed_platform_token(claims: &PlatClaims, pak: &SigningKey) -> PlatformToken
+    {
+        let bytes = builder::build_platform_token(claims, &[], pak).expect("sign platform token");
+
+        let mut token = PlatformToken::new();
+        token.cose_sign1 = CoseSign1::from_tagged_slice(&bytes).expect("decode platform COSE_Sign1");
+
+        fill(&mut token.token_claims, CCA_PLAT_CHALLENGE, ClaimData::Bstr(claims.challenge.clone()));
+        fill(&mut token.token_claims, CCA_PLAT_VERIFICATION_SERVICE, ClaimData::Text(claims.verification_service.clone()));
+        fill(&mut token.token_claims, CCA_PLAT_PROFILE, ClaimData::Text(claims.profile.clone()));
+        fill(&mut token.token_claims, CCA_PLAT_INSTANCE_ID, ClaimData::Bstr(claims.instance_id.clone()));
+        fill(&mut token.token_claims, CCA_PLAT_IMPLEMENTATION_ID, ClaimData::Bstr(claims.implementation_id.clone()));
+        fill(&mut token.token_claims, CCA_PLAT_SECURITY_LIFECYCLE, ClaimData::Int64(claims.lifecycle));
+        fill(&mut token.token_claims, CCA_PLAT_CONFIGURATION, ClaimData::Bstr(claims.configuration.clone()));
+        fill(&mut token.token_claims, CCA_PLAT_HASH_ALGO_ID, ClaimData::Text(claims.hash_algo.clone()));
+
+        token
+    }
+
+    #[test]
+    fn round_trip_build_and_verify_succeeds()
+    {
+        let rak = SigningKey::random(&mut OsRng);
+        let pak = SigningKey::random(&mut OsRng);
+        let rak_pub_key = rak.verifying_key().to_sec1_bytes().to_vec();
+        let pak_pub_key = pak.verifying_key().to_sec1_bytes().to_vec();
+
+        let realm_claims = sample_realm_claims(rak_pub_key);
+        let plat_challenge = crypto::hash(&realm_claims.pub_key_hash_algo, &realm_claims.pub_key).unwrap();
+        let plat_claims = sample_plat_claims(plat_challenge);
+
+        let realm_token = signed_realm_token(&realm_claims, &rak);
+        let platform_token = signed_platform_token(&plat_claims, &pak);
+
+        let verified = verify_attestation_token(&platform_token, &realm_token, &pak_pub_key)
+            .expect("a self-consistent token pair must verify");
+
+        assert!(verified.binding.bound);
+    }
+
+    #[test]
+    fn key_binding_mismatch_is_carried_through_the_error()
+    {
+        let rak = SigningKey::random(&mut OsRng);
+        let pak = SigningKey::random(&mut OsRng);
+        let rak_pub_key = rak.verifying_key().to_sec1_bytes().to_vec();
+        let pak_pub_key = pak.verifying_key().to_sec1_bytes().to_vec();
+
+        let realm_claims = sample_realm_claims(rak_pub_key);
+        // Deliberately unrelated to the realm key hash.
+        let plat_claims = sample_plat_claims(vec![0xffu8; 32]);
+
+        let realm_token = signed_realm_token(&realm_claims, &rak);
+        let platform_token = signed_platform_token(&plat_claims, &pak);
+
+        match verify_attestation_token(&platform_token, &realm_token, &pak_pub_key) {
+            Err(TokenError::KeyBindingMismatch(result)) => {
+                assert!(!result.bound);
+                assert_ne!(result.computed_hash, result.expected_hash);
+            }
+            other => panic!("expected KeyBindingMismatch, got {:?}", other),
+        }
+    }
+}